@@ -0,0 +1,146 @@
+use anyhow::Context;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::io::AsRawFd;
+
+const KDGETLED: libc::c_ulong = 0x4b31;
+const KDSETLED: libc::c_ulong = 0x4b32;
+const KDGKBLED: libc::c_ulong = 0x4B64;
+const KDSKBLED: libc::c_ulong = 0x4B65;
+
+const LED_SCR: libc::c_char = 0b001;
+const LED_NUM: libc::c_char = 0b010;
+const LED_CAP: libc::c_char = 0b100;
+
+/// Retries an ioctl-returning closure on `EINTR` and turns a `-1` return
+/// into the matching `io::Error`.
+fn cvt<F: FnMut() -> libc::c_int>(mut f: F) -> io::Result<libc::c_int> {
+    loop {
+        let ret = f();
+        if ret == -1 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err);
+        }
+        return Ok(ret);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Indicator {
+    ScrollLock,
+    NumLock,
+    CapsLock,
+}
+
+impl Indicator {
+    fn bit(self) -> libc::c_char {
+        match self {
+            Indicator::ScrollLock => LED_SCR,
+            Indicator::NumLock => LED_NUM,
+            Indicator::CapsLock => LED_CAP,
+        }
+    }
+}
+
+#[derive(clap::Args)]
+pub struct SetIndicator {
+    /// Which indicator LED to act on
+    pub indicator: Indicator,
+    /// Turn the indicator on or off. If omitted, toggles its current state.
+    pub on: Option<bool>,
+    /// Act on the persistent default flags (KDGKBLED/KDSKBLED) instead of
+    /// the current state (KDGETLED/KDSETLED)
+    #[clap(long)]
+    pub persistent: bool,
+}
+
+/// Drives the caps/num/scroll lock indicator LEDs through the console's
+/// `KDGETLED`/`KDSETLED` ioctls, since these aren't reliably reachable
+/// through `/sys/class/leds/`.
+pub struct ConsoleLed {
+    console: File,
+}
+
+impl ConsoleLed {
+    /// Opens `/dev/console`, falling back to the controlling tty
+    /// (`/dev/tty`) when the console device isn't accessible.
+    pub fn open() -> anyhow::Result<Self> {
+        let open = |path: &str| OpenOptions::new().read(true).write(true).open(path);
+
+        let console = open("/dev/console")
+            .or_else(|_| open("/dev/tty"))
+            .context("Couldn't open /dev/console or the controlling tty (/dev/tty)")?;
+
+        Ok(Self { console })
+    }
+
+    fn get_state(&self) -> anyhow::Result<libc::c_char> {
+        let mut state: libc::c_char = 0;
+        cvt(|| unsafe { libc::ioctl(self.console.as_raw_fd(), KDGETLED, &mut state as *mut _) })
+            .context("KDGETLED ioctl failed")?;
+        Ok(state)
+    }
+
+    fn set_state(&self, state: libc::c_char) -> anyhow::Result<()> {
+        cvt(|| unsafe { libc::ioctl(self.console.as_raw_fd(), KDSETLED, state as libc::c_ulong) })
+            .context("KDSETLED ioctl failed")?;
+        Ok(())
+    }
+
+    pub fn is_set(&self, indicator: Indicator) -> anyhow::Result<bool> {
+        Ok(self.get_state()? & indicator.bit() != 0)
+    }
+
+    pub fn set(&self, indicator: Indicator, on: bool) -> anyhow::Result<()> {
+        let mut state = self.get_state()?;
+        if on {
+            state |= indicator.bit();
+        } else {
+            state &= !indicator.bit();
+        }
+        self.set_state(state)
+    }
+
+    pub fn toggle(&self, indicator: Indicator) -> anyhow::Result<()> {
+        let is_set = self.is_set(indicator)?;
+        self.set(indicator, !is_set)
+    }
+
+    /// Reads the persistent default flags (`KDGKBLED`), i.e. the state the
+    /// indicators revert to once the kernel stops overriding them.
+    pub fn get_default_state(&self) -> anyhow::Result<libc::c_char> {
+        let mut state: libc::c_char = 0;
+        cvt(|| unsafe { libc::ioctl(self.console.as_raw_fd(), KDGKBLED, &mut state as *mut _) })
+            .context("KDGKBLED ioctl failed")?;
+        Ok(state)
+    }
+
+    /// Writes the persistent default flags (`KDSKBLED`).
+    pub fn set_default_state(&self, state: libc::c_char) -> anyhow::Result<()> {
+        cvt(|| unsafe { libc::ioctl(self.console.as_raw_fd(), KDSKBLED, state as libc::c_ulong) })
+            .context("KDSKBLED ioctl failed")?;
+        Ok(())
+    }
+
+    pub fn is_default_set(&self, indicator: Indicator) -> anyhow::Result<bool> {
+        Ok(self.get_default_state()? & indicator.bit() != 0)
+    }
+
+    pub fn set_default(&self, indicator: Indicator, on: bool) -> anyhow::Result<()> {
+        let mut state = self.get_default_state()?;
+        if on {
+            state |= indicator.bit();
+        } else {
+            state &= !indicator.bit();
+        }
+        self.set_default_state(state)
+    }
+
+    pub fn toggle_default(&self, indicator: Indicator) -> anyhow::Result<()> {
+        let is_set = self.is_default_set(indicator)?;
+        self.set_default(indicator, !is_set)
+    }
+}
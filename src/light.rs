@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Context};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use crate::Stepping;
 
@@ -78,6 +79,66 @@ impl Lights {
 
         devices
     }
+
+    /// Path of the brightness state file: `$XDG_STATE_HOME/licht.csv` if
+    /// set, falling back to `/run/licht.csv`.
+    fn state_path() -> PathBuf {
+        std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("/run"))
+            .join("licht.csv")
+    }
+
+    /// Snapshots every device's current brightness to the state file,
+    /// keyed by device name and class, in the same column order as
+    /// `get info --csv`.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let mut csv = String::new();
+        for device in &self.devices {
+            csv.push_str(&format!(
+                "{},{},{},{:.0}%,{}\n",
+                device.get_name(),
+                device.get_class(),
+                device.brightness,
+                device.get_percent() * 100.0f32,
+                device.max_brightness,
+            ));
+        }
+
+        let path = Self::state_path();
+        std::fs::write(&path, csv)
+            .with_context(|| format!("Couldn't write state file '{}'", path.display()))
+    }
+
+    /// Writes each device's brightness from the state file back through
+    /// `Light::write`, matching devices by name and class.
+    pub fn restore(&mut self) -> anyhow::Result<()> {
+        let path = Self::state_path();
+        let csv = std::fs::read_to_string(&path)
+            .with_context(|| format!("Couldn't read state file '{}'", path.display()))?;
+
+        for line in csv.lines() {
+            let columns = line.split(',').collect::<Vec<_>>();
+            let name = *columns.first().context("State file line missing name")?;
+            let class = *columns.get(1).context("State file line missing class")?;
+            let brightness: usize = columns
+                .get(2)
+                .context("State file line missing brightness")?
+                .parse()
+                .context("State file has a non-numeric brightness")?;
+
+            if let Some(device) = self
+                .devices
+                .iter_mut()
+                .find(|device| device.get_name() == name && device.get_class() == class)
+            {
+                device.brightness = brightness.clamp(0, device.max_brightness);
+                device.write()?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Clone)]
@@ -86,6 +147,22 @@ pub struct Light {
     pub max_brightness: usize,
     pub device_path: PathBuf,
     pub class: DeviceClass,
+    /// Per-channel `(name, intensity)` pairs from the kernel multicolor
+    /// framework (`multi_index`/`multi_intensity`), in file order. `None`
+    /// when the device doesn't expose multicolor channels.
+    pub multi_channels: Option<Vec<(String, usize)>>,
+}
+
+/// Arguments for `set color`: an R,G,B triple of 0-100 percentages, mapped
+/// onto the device's multicolor channels by name.
+#[derive(clap::Args)]
+pub struct SetColor {
+    /// Red channel, as a percentage (0-100) of full intensity
+    pub red: u8,
+    /// Green channel, as a percentage (0-100) of full intensity
+    pub green: u8,
+    /// Blue channel, as a percentage (0-100) of full intensity
+    pub blue: u8,
 }
 
 impl Light {
@@ -94,6 +171,19 @@ impl Light {
         text.replace('\n', "").parse().context("parse failure")
     }
 
+    fn read_multi_channels(device_path: &Path) -> Option<Vec<(String, usize)>> {
+        let index = std::fs::read_to_string(device_path.join("multi_index")).ok()?;
+        let intensity = std::fs::read_to_string(device_path.join("multi_intensity")).ok()?;
+
+        let names = index.split_whitespace().map(str::to_owned);
+        let values = intensity
+            .split_whitespace()
+            .map(|value| value.parse::<usize>().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(names.zip(values).collect())
+    }
+
     pub fn default() -> anyhow::Result<Self> {
         let device_path = Path::new(DeviceClass::BACKLIGHT_PATH)
             .read_dir()
@@ -115,6 +205,7 @@ impl Light {
             max_brightness: Self::read_to_usize(device_path.join("max_brightness"))?,
             device_path: device_path.to_owned(),
             class: DeviceClass::from_path(&device_path.to_string_lossy())?,
+            multi_channels: Self::read_multi_channels(device_path),
         })
     }
 
@@ -149,18 +240,111 @@ impl Light {
             .expect("Invalid device name")
     }
 
-    pub fn calculate_brightness(&mut self, stepping: &dyn Stepping, min: usize) {
-        let new_brightness = stepping
+    /// Gamma exponent used wherever a value needs to map onto brightness
+    /// perceptually (`x^exponent`, the same form `Parabolic` uses). This is
+    /// a fixed value; it does not follow the exponents passed to
+    /// `Parabolic`/`Blend`. Shared by `fade_to` (easing frames) and
+    /// `set_color` (scaling channels).
+    const PERCEPTUAL_GAMMA: f32 = 2.2;
+
+    fn target_brightness(&self, stepping: &dyn Stepping, min: usize) -> usize {
+        stepping
             .calculate(self.brightness, self.max_brightness)
-            .clamp(min as f32, self.max_brightness as f32);
+            .clamp(min as f32, self.max_brightness as f32) as usize
+    }
+
+    pub fn calculate_brightness(&mut self, stepping: &dyn Stepping, min: usize) {
+        let new_brightness = self.target_brightness(stepping, min);
 
         verbose!("{}", self);
         verbose!(
             "{}% -> {}%",
             (self.get_percent() * 100.0f32).round(),
-            (new_brightness / self.max_brightness as f32 * 100.0f32).round()
+            (new_brightness as f32 / self.max_brightness as f32 * 100.0f32).round()
+        );
+        self.brightness = new_brightness;
+    }
+
+    /// Ramps from the current brightness to the target computed from
+    /// `stepping` over `duration`, writing an intermediate frame every
+    /// `1/fps` seconds. Frames are eased with a perceptual gamma curve
+    /// rather than interpolated linearly, so the ramp looks smooth.
+    pub fn fade_to(
+        &mut self,
+        stepping: &dyn Stepping,
+        min: usize,
+        duration: Duration,
+        fps: u32,
+        dry_run: bool,
+    ) -> anyhow::Result<()> {
+        let start = self.brightness;
+        let target = self.target_brightness(stepping, min);
+
+        verbose!("{}", self);
+        verbose!(
+            "{}% -> {}% over {:?}",
+            (self.get_percent() * 100.0f32).round(),
+            (target as f32 / self.max_brightness as f32 * 100.0f32).round(),
+            duration
         );
-        self.brightness = new_brightness as usize;
+
+        let frame_count = ((duration.as_secs_f32() * fps as f32).round() as usize)
+            .clamp(1, u32::MAX as usize);
+        let frame_count = frame_count as u32;
+        let frame_time = duration / frame_count;
+        let start_time = Instant::now();
+
+        for frame in 1..=frame_count {
+            let progress = frame as f32 / frame_count as f32;
+            let eased = progress.powf(Self::PERCEPTUAL_GAMMA);
+            let value = start as f32 + (target as f32 - start as f32) * eased;
+
+            self.brightness = (value.round() as usize).clamp(min, self.max_brightness);
+
+            if !dry_run {
+                self.write()?;
+            }
+
+            if let Some(remaining) = (frame_time * frame).checked_sub(start_time.elapsed()) {
+                std::thread::sleep(remaining);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Maps an R,G,B triple of 0-100 percentages onto the device's
+    /// multicolor channels by name, gamma-correcting each channel into
+    /// the device's brightness range. The kernel multicolor ABI scales
+    /// `multi_intensity` by the overall `brightness`, so if the device is
+    /// currently off this also raises `brightness` to `max_brightness` —
+    /// otherwise the color would be written but stay invisible.
+    pub fn set_color(&mut self, red: u8, green: u8, blue: u8) -> anyhow::Result<()> {
+        let max = self.max_brightness;
+        let channels = self
+            .multi_channels
+            .as_mut()
+            .context("Device has no multi_index/multi_intensity channels")?;
+
+        let scale = |percent: u8| {
+            let x = percent.min(100) as f32 / 100.0f32;
+            (x.powf(Self::PERCEPTUAL_GAMMA) * max as f32).round() as usize
+        };
+
+        for (name, value) in channels.iter_mut() {
+            *value = match name.as_str() {
+                "red" => scale(red),
+                "green" => scale(green),
+                "blue" => scale(blue),
+                _ => *value,
+            };
+        }
+
+        if self.brightness == 0 {
+            self.brightness = self.max_brightness;
+        }
+
+        Ok(())
     }
 
     pub fn write(&self) -> anyhow::Result<()> {
@@ -168,7 +352,23 @@ impl Light {
             &self.device_path.join("brightness"),
             &self.brightness.to_string().as_bytes(),
         )
-        .context("writing brightness failed")
+        .context("writing brightness failed")?;
+
+        if let Some(channels) = &self.multi_channels {
+            let multi_intensity = channels
+                .iter()
+                .map(|(_, value)| value.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            std::fs::write(
+                &self.device_path.join("multi_intensity"),
+                multi_intensity.as_bytes(),
+            )
+            .context("writing multi_intensity failed")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -184,3 +384,53 @@ impl std::fmt::Display for Light {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_device(root: &Path, name: &str, brightness: usize, max_brightness: usize) -> Light {
+        let device_path = root.join(name);
+        std::fs::create_dir_all(&device_path).unwrap();
+        std::fs::write(device_path.join("brightness"), brightness.to_string()).unwrap();
+        std::fs::write(
+            device_path.join("max_brightness"),
+            max_brightness.to_string(),
+        )
+        .unwrap();
+
+        Light {
+            brightness,
+            max_brightness,
+            device_path,
+            class: DeviceClass::Backlight,
+            multi_channels: None,
+        }
+    }
+
+    #[test]
+    fn save_then_restore_round_trips_brightness() {
+        let root = std::env::temp_dir().join(format!(
+            "licht-test-save-restore-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        std::env::set_var("XDG_STATE_HOME", &root);
+
+        let mut lights = Lights {
+            devices: vec![make_device(&root, "test0", 50, 255)],
+        };
+        lights.save().unwrap();
+
+        lights.devices[0].brightness = 0;
+        lights.restore().unwrap();
+
+        assert_eq!(lights.devices[0].brightness, 50);
+        assert_eq!(
+            std::fs::read_to_string(lights.devices[0].device_path.join("brightness")).unwrap(),
+            "50"
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -9,6 +9,9 @@ use light::Light;
 mod stepping;
 use stepping::Stepping;
 
+mod console_led;
+use console_led::ConsoleLed;
+
 #[derive(Parser)]
 struct Cli {
     #[clap(subcommand)]
@@ -47,6 +50,15 @@ enum Action {
         /// dry-run implies verbose
         #[clap(value_parser, long, display_order = 7)]
         dry_run: bool,
+
+        /// Fade to the target brightness over this many milliseconds
+        /// instead of jumping to it immediately. 0 disables fading.
+        #[clap(value_parser, long, default_value("0"), display_order = 8)]
+        duration: u64,
+
+        /// Frames per second to emit while fading
+        #[clap(value_parser, long, default_value("60"), display_order = 9)]
+        fps: u32,
     },
 }
 
@@ -83,6 +95,21 @@ enum SetMode {
         #[clap(flatten)]
         parabolic: stepping::Parabolic,
     },
+    /// Toggles or sets a keyboard indicator LED (caps/num/scroll lock)
+    /// through the console, bypassing sysfs entirely.
+    Indicator {
+        #[clap(flatten)]
+        indicator: console_led::SetIndicator,
+    },
+    /// Sets a gamma-corrected RGB color (0-100 percent per channel) on a
+    /// device exposing multi_index/multi_intensity (the kernel multicolor
+    /// LED framework).
+    Color {
+        #[clap(flatten)]
+        color: light::SetColor,
+    },
+    /// Writes back the brightness values saved by `get save`
+    Restore,
 }
 
 impl SetMode {
@@ -93,6 +120,9 @@ impl SetMode {
             Self::Blend { blend } => blend,
             Self::Geometric { geometric } => geometric,
             Self::Parabolic { parabolic } => parabolic,
+            Self::Indicator { .. } => unreachable!("Indicator is handled separately in main"),
+            Self::Color { .. } => unreachable!("Color is handled separately in main"),
+            Self::Restore => unreachable!("Restore is handled separately in main"),
         }
     }
 }
@@ -128,6 +158,9 @@ enum GetMode {
     },
     /// List availble backlight devices
     List,
+    /// Snapshot every discovered device's current brightness to the state
+    /// file, so it can be restored later with `set restore`.
+    Save,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -136,10 +169,13 @@ fn main() -> anyhow::Result<()> {
     match cli.action {
         Action::Get { mode } => match mode {
             GetMode::List => {
-                for device in light::discover_all()? {
+                for device in light::Lights::discover_all()?.devices {
                     println!("{}", device);
                 }
             }
+            GetMode::Save => {
+                light::Lights::discover_all()?.save()?;
+            }
             GetMode::Info {
                 name,
                 class,
@@ -188,6 +224,8 @@ fn main() -> anyhow::Result<()> {
             mut verbose,
             dry_run,
             device_name,
+            duration,
+            fps,
         } => {
             if dry_run {
                 verbose = true;
@@ -197,11 +235,53 @@ fn main() -> anyhow::Result<()> {
                 verbose_enable!();
             }
 
+            if let SetMode::Restore = &mode {
+                if !dry_run {
+                    light::Lights::discover_all()?.restore()?;
+                }
+                return Ok(());
+            }
+
+            if let SetMode::Indicator { indicator } = &mode {
+                let (indicator, on, persistent) =
+                    (indicator.indicator, indicator.on, indicator.persistent);
+                let console = ConsoleLed::open()?;
+                match (on, persistent) {
+                    (Some(on), false) => {
+                        verbose!("Setting indicator to {}", on);
+                        if !dry_run {
+                            console.set(indicator, on)?;
+                        }
+                    }
+                    (Some(on), true) => {
+                        verbose!("Setting persistent default indicator flag to {}", on);
+                        if !dry_run {
+                            console.set_default(indicator, on)?;
+                        }
+                    }
+                    (None, false) => {
+                        verbose!("Toggling indicator");
+                        if !dry_run {
+                            console.toggle(indicator)?;
+                        }
+                    }
+                    (None, true) => {
+                        verbose!("Toggling persistent default indicator flag");
+                        if !dry_run {
+                            console.toggle_default(indicator)?;
+                        }
+                    }
+                }
+                return Ok(());
+            }
+
             let mut chosen_devices = Vec::new();
 
             if all {
                 chosen_devices.extend(
-                    light::discover_backlights()?
+                    light::Lights::discover_backlights()?
+                        .devices
+                        .into_iter()
                         .filter(|dev| matches!(dev.class, light::DeviceClass::Backlight)),
                 );
                 if chosen_devices.is_empty() {
@@ -214,9 +294,24 @@ fn main() -> anyhow::Result<()> {
             }
 
             for mut device in chosen_devices {
-                device.calculate_brightness(mode.get_stepping(), min_brightness);
-                if dry_run {
-                    device.write()?;
+                if let SetMode::Color { color } = &mode {
+                    device.set_color(color.red, color.green, color.blue)?;
+                    if !dry_run {
+                        device.write()?;
+                    }
+                } else if duration > 0 {
+                    device.fade_to(
+                        mode.get_stepping(),
+                        min_brightness,
+                        std::time::Duration::from_millis(duration),
+                        fps,
+                        dry_run,
+                    )?;
+                } else {
+                    device.calculate_brightness(mode.get_stepping(), min_brightness);
+                    if !dry_run {
+                        device.write()?;
+                    }
                 }
             }
         }
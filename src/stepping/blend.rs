@@ -8,6 +8,46 @@ pub struct Blend {
     pub step: i32,
 }
 
+impl Blend {
+    const MAX_ITERATIONS: u32 = 64;
+    const EPSILON: f32 = 1e-6;
+
+    /// Finds `x` in `[0, 1]` with `h(x) == target`, where `h` is monotonic
+    /// on that interval (`h(0) == 0`, `h(1) == 1`). Takes a Newton step
+    /// when the derivative is finite, nonzero, and keeps the iterate
+    /// strictly inside the current bracket; otherwise bisects. This
+    /// guarantees termination and never leaves `[0, 1]`, unlike a bare
+    /// Newton loop which can diverge or loop forever near the interval's
+    /// endpoints.
+    fn solve(h: impl Fn(f32) -> f32, h_dash: impl Fn(f32) -> f32, target: f32) -> f32 {
+        let (mut lo, mut hi) = (0.0f32, 1.0f32);
+        let mut x = target;
+
+        for _ in 0..Self::MAX_ITERATIONS {
+            let error = h(x) - target;
+            if error.abs() < Self::EPSILON {
+                break;
+            }
+
+            if error > 0.0f32 {
+                hi = x;
+            } else {
+                lo = x;
+            }
+
+            let newton_x = x - error / h_dash(x);
+
+            x = if newton_x.is_finite() && newton_x > lo && newton_x < hi {
+                newton_x
+            } else {
+                lo + (hi - lo) / 2.0f32
+            };
+        }
+
+        x.clamp(0.0f32, 1.0f32)
+    }
+}
+
 impl Stepping for Blend {
     #[inline]
     fn calculate(&self, cur: usize, max: usize) -> f32 {
@@ -18,30 +58,22 @@ impl Stepping for Blend {
         }
 
         let f = |x: f32| x.powf(self.a);
-        let f_inverse = |y: usize| (y as f32/max as f32).powf(self.a.recip());
         let g = |x: f32| 1.0f32 - (1.0f32 - x).powf(self.b.recip());
-        let g_inverse = |y: usize| 1.0f32 - (1.0f32 - (y as f32/max as f32)).powf(self.b);
-        let h = |x: f32| {
-            self.ratio*f(x) + (1.0f32-self.ratio)*g(x)
-        };
+        let h = |x: f32| self.ratio * f(x) + (1.0f32 - self.ratio) * g(x);
 
         let h_dash = |x: f32| {
-            self.a*self.ratio*x.powf(self.a - 1.0f32) - ((self.ratio - 1.0f32)*(1.0f32 - x).powf(self.b.recip() - 1.0f32))/self.b
+            self.a * self.ratio * x.powf(self.a - 1.0f32)
+                - ((self.ratio - 1.0f32) * (1.0f32 - x).powf(self.b.recip() - 1.0f32)) / self.b
         };
 
-        let mut cur_x = self.ratio * f_inverse(cur) + (1.0f32 - self.ratio) * g_inverse(cur);
+        let cur_x = Self::solve(h, h_dash, cur as f32 / max as f32);
 
-        while (h(cur_x) * max as f32 - cur as f32) as i32 != 0 {
-            // newton's method
-            cur_x = cur_x - (h(cur_x) - cur as f32/max as f32)/h_dash(cur_x);
-        }
-        
         let new_x = cur_x + self.step as f32 / 100.0f32;
 
         if new_x >= 1.0f32 {
             return max as f32;
         } else if new_x <= 0.0f32 {
-            return 0.0f32
+            return 0.0f32;
         }
 
         h(new_x) * max as f32